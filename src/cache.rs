@@ -0,0 +1,122 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::{Duration, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// On-disk cache for arbitrary serializable market data, keyed by an
+/// arbitrary string such as a stock symbol or currency pair.
+pub struct DiskCache {
+    cache_dir: PathBuf,
+    expire_time: Duration,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry<T> {
+    fetched_at: chrono::NaiveDateTime,
+    value: T,
+}
+
+impl DiskCache {
+    pub fn new(cache_dir: impl Into<PathBuf>, expire_time: Duration) -> Self {
+        Self {
+            cache_dir: cache_dir.into(),
+            expire_time,
+        }
+    }
+
+    fn path_for(&self, key: &str) -> PathBuf {
+        self.cache_dir.join(format!("{}.json", key))
+    }
+
+    /// Returns the cached value for `key`, or `None` if there is no cache
+    /// file yet or it is older than `expire_time`.
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let entry: CacheEntry<T> = read_entry(&self.path_for(key))?;
+        let age = Utc::now().naive_utc() - entry.fetched_at;
+        (age <= self.expire_time).then_some(entry.value)
+    }
+
+    /// Persists `value` for `key`, overwriting any previous cache file.
+    pub fn put<T: Serialize + Clone>(&self, key: &str, value: &T) {
+        if fs::create_dir_all(&self.cache_dir).is_err() {
+            return;
+        }
+        let entry = CacheEntry {
+            fetched_at: Utc::now().naive_utc(),
+            value: value.clone(),
+        };
+        if let Ok(contents) = serde_json::to_string(&entry) {
+            let _ = fs::write(self.path_for(key), contents);
+        }
+    }
+}
+
+fn read_entry<T: DeserializeOwned>(path: &Path) -> Option<CacheEntry<T>> {
+    let contents = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use rust_decimal::Decimal;
+    use std::{collections::BTreeMap, str::FromStr};
+
+    fn unique_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "schwab-cgt-calculator-cache-test-{}-{}",
+            name,
+            std::process::id()
+        ))
+    }
+
+    fn sample_series() -> BTreeMap<NaiveDate, Decimal> {
+        let mut series = BTreeMap::new();
+        series.insert(
+            NaiveDate::from_ymd(2024, 1, 1),
+            Decimal::from_str("1.5").unwrap(),
+        );
+        series
+    }
+
+    #[test]
+    fn test_get_returns_none_when_nothing_cached() {
+        let cache = DiskCache::new(unique_dir("miss"), Duration::hours(24));
+        assert_eq!(cache.get::<BTreeMap<NaiveDate, Decimal>>("GOOG"), None);
+    }
+
+    #[test]
+    fn test_put_then_get_returns_the_cached_series() {
+        let dir = unique_dir("hit");
+        let cache = DiskCache::new(dir.clone(), Duration::hours(24));
+        let series = sample_series();
+
+        cache.put("GOOG", &series);
+
+        assert_eq!(cache.get("GOOG"), Some(series));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_get_returns_none_once_the_entry_has_expired() {
+        let dir = unique_dir("expiry");
+        let cache = DiskCache::new(dir.clone(), Duration::hours(24));
+        fs::create_dir_all(&dir).unwrap();
+        let stale_entry = CacheEntry {
+            fetched_at: Utc::now().naive_utc() - Duration::hours(25),
+            value: sample_series(),
+        };
+        fs::write(
+            cache.path_for("GOOG"),
+            serde_json::to_string(&stale_entry).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(cache.get::<BTreeMap<NaiveDate, Decimal>>("GOOG"), None);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,19 +1,32 @@
 use std::fmt::{Display, Result};
 
 use crate::{
-    currency::exchange_rates::{ExchangeRateProvider, YahooExchangeRateProvider},
-    schwab::equity_award_center::EquityAwardCenter,
-    stock::prices::{StockPriceProvider, YahooStockPriceProvider},
+    config::Config,
+    currency::exchange_rates::{build_exchange_rate_provider, ExchangeRateProvider},
+    schwab::equity_award_center::{EquityAward, EquityAwardCenter},
+    stock::dividends::{build_dividend_provider, DividendProvider},
+    stock::prices::{build_stock_price_provider, StockPriceProvider},
+    stock::splits::{build_split_provider, cumulative_split_factor, SplitProvider},
+    tax_year,
 };
-use chrono::{Duration, NaiveDate};
+use anyhow::Context;
+use chrono::{Duration, NaiveDate, Utc};
+use rust_decimal::Decimal;
 
 pub struct CGTCalculatorResult {
-    pub cgt: f64,
-    pub proceeds: f64,
-    pub bed_and_breakfast_cost: f64,
-    pub section_104_holding_cost: f64,
-    pub amount_subject_to_cgt: f64,
-    pub cgt_rate: f64,
+    pub symbol: String,
+    pub sell_date: NaiveDate,
+    pub shares_sold: Decimal,
+    pub cgt: Decimal,
+    pub proceeds: Decimal,
+    pub same_day_cost: Decimal,
+    pub bed_and_breakfast_cost: Decimal,
+    pub section_104_holding_cost: Decimal,
+    pub amount_subject_to_cgt: Decimal,
+    pub amount_taxed_at_basic_rate: Decimal,
+    pub amount_taxed_at_higher_rate: Decimal,
+    pub basic_rate: Decimal,
+    pub higher_rate: Decimal,
 }
 
 impl Display for CGTCalculatorResult {
@@ -26,124 +39,175 @@ CGT due: £{:.2}
 =============================
 Breakdown:
 * Proceeds: £{:.2}
+* Same Day Cost: £{:.2}
 * Bed & Breakfast Cost: £{:.2}
 * Section 104 Holdings Cost: £{:.2}
 * Net proceeds: £{:.2}
 * Amount subject to CGT: £{:.2}
-* CGT Rate: {}%",
-            self.cgt,
-            self.proceeds,
-            self.bed_and_breakfast_cost,
-            self.section_104_holding_cost,
-            self.proceeds - self.bed_and_breakfast_cost - self.section_104_holding_cost,
-            self.amount_subject_to_cgt,
-            self.cgt_rate * 100.0,
+* Taxed at basic rate ({}%): £{:.2}
+* Taxed at higher rate ({}%): £{:.2}",
+            self.cgt.round_dp(2),
+            self.proceeds.round_dp(2),
+            self.same_day_cost.round_dp(2),
+            self.bed_and_breakfast_cost.round_dp(2),
+            self.section_104_holding_cost.round_dp(2),
+            (self.proceeds
+                - self.same_day_cost
+                - self.bed_and_breakfast_cost
+                - self.section_104_holding_cost)
+                .round_dp(2),
+            self.amount_subject_to_cgt.round_dp(2),
+            (self.basic_rate * Decimal::from(100)).round_dp(2),
+            self.amount_taxed_at_basic_rate.round_dp(2),
+            (self.higher_rate * Decimal::from(100)).round_dp(2),
+            self.amount_taxed_at_higher_rate.round_dp(2),
         )
     }
 }
 
 pub struct CGTCalculator {
-    annual_exemption_amount: f64,
-    cgt_rate: f64,
     equity_award_center: EquityAwardCenter,
     stock_price_provider: Box<dyn StockPriceProvider>,
     exchange_rate_provider: Box<dyn ExchangeRateProvider>,
+    split_provider: Box<dyn SplitProvider>,
+    dividend_provider: Option<Box<dyn DividendProvider>>,
 }
 
 impl CGTCalculator {
     pub async fn new(
         symbol: &str,
         equity_award_center: EquityAwardCenter,
-        annual_exemption_amount: f64,
-        cgt_rate: f64,
-    ) -> Self {
-        Self {
-            annual_exemption_amount,
-            cgt_rate,
+        config: &Config,
+    ) -> anyhow::Result<Self> {
+        let dividend_provider: Option<Box<dyn DividendProvider>> = if config.drip_enabled {
+            Some(build_dividend_provider(symbol, config).await?)
+        } else {
+            None
+        };
+
+        Ok(Self {
             equity_award_center,
-            stock_price_provider: Box::new(YahooStockPriceProvider::new(symbol).await),
-            exchange_rate_provider: Box::new(YahooExchangeRateProvider::new().await),
+            stock_price_provider: build_stock_price_provider(symbol, config).await,
+            exchange_rate_provider: build_exchange_rate_provider(config).await,
+            split_provider: build_split_provider(symbol, config).await?,
+            dividend_provider,
+        })
+    }
+
+    /// Cumulative split factor from `award.date_acquired`'s basis to
+    /// `sell_date`'s, inverted for a bed & breakfast award acquired after
+    /// `sell_date`.
+    fn split_adjustment_factor(&self, award: &EquityAward, sell_date: &NaiveDate) -> Decimal {
+        let splits = self.split_provider.get_splits(&award.symbol);
+        if award.date_acquired <= sell_date.to_owned() {
+            cumulative_split_factor(&splits, &award.date_acquired, sell_date)
+        } else {
+            Decimal::ONE / cumulative_split_factor(&splits, sell_date, &award.date_acquired)
         }
     }
 
+    /// `award.available_to_sell` normalised to `sell_date`'s share basis.
+    fn split_adjusted_available_to_sell(&self, award: &EquityAward, sell_date: &NaiveDate) -> Decimal {
+        award.available_to_sell * self.split_adjustment_factor(award, sell_date)
+    }
+
+    /// `award.acquisition_price` normalised to `sell_date`'s share basis.
+    fn split_adjusted_acquisition_price(&self, award: &EquityAward, sell_date: &NaiveDate) -> Decimal {
+        award.acquisition_price / self.split_adjustment_factor(award, sell_date)
+    }
+
+    /// Computes the CGT due on a disposal, picking the annual exempt amount
+    /// and basic/higher rates from `sell_date`'s UK tax year. Errors if
+    /// `sell_date` falls in a tax year not covered by
+    /// [`tax_year::rates_for_date`].
     pub fn calculate_cgt(
         &self,
         symbol: &str,
-        shares_to_sell: f64,
+        shares_to_sell: Decimal,
         sell_date: &NaiveDate,
-    ) -> CGTCalculatorResult {
-        self.validate_sufficient_holdings_at_sell_date(symbol, shares_to_sell, sell_date);
+        remaining_basic_rate_band: Decimal,
+    ) -> anyhow::Result<CGTCalculatorResult> {
+        let tax_year_rates = tax_year::rates_for_date(sell_date).with_context(|| {
+            format!("No known CGT rates for the tax year containing {:?}", sell_date)
+        })?;
 
         let proceeds = self.calculate_proceeds(symbol, shares_to_sell, sell_date);
-        let (bed_and_breakfast_cost, section_104_holding_cost) =
+        let (same_day_cost, bed_and_breakfast_cost, section_104_holding_cost) =
             self.calculate_costs(symbol, shares_to_sell, sell_date);
         let amount_subject_to_cgt = (proceeds
+            - same_day_cost
             - bed_and_breakfast_cost
             - section_104_holding_cost
-            - self.annual_exemption_amount)
-            .max(0.0);
-        let cgt = amount_subject_to_cgt * self.cgt_rate;
+            - tax_year_rates.annual_exempt_amount)
+            .max(Decimal::ZERO);
+
+        let amount_taxed_at_basic_rate =
+            amount_subject_to_cgt.min(remaining_basic_rate_band.max(Decimal::ZERO));
+        let amount_taxed_at_higher_rate = amount_subject_to_cgt - amount_taxed_at_basic_rate;
+        let cgt = amount_taxed_at_basic_rate * tax_year_rates.basic_rate
+            + amount_taxed_at_higher_rate * tax_year_rates.higher_rate;
 
-        CGTCalculatorResult {
+        Ok(CGTCalculatorResult {
+            symbol: symbol.to_owned(),
+            sell_date: sell_date.to_owned(),
+            shares_sold: shares_to_sell,
             cgt,
             proceeds,
+            same_day_cost,
             bed_and_breakfast_cost,
             section_104_holding_cost,
             amount_subject_to_cgt,
-            cgt_rate: self.cgt_rate,
-        }
+            amount_taxed_at_basic_rate,
+            amount_taxed_at_higher_rate,
+            basic_rate: tax_year_rates.basic_rate,
+            higher_rate: tax_year_rates.higher_rate,
+        })
     }
 
-    fn validate_sufficient_holdings_at_sell_date(
+    /// Undoes `get_historic_price`'s Adj Close split adjustment before
+    /// multiplying by `shares_to_sell`'s (real, sell-date-basis) count.
+    fn calculate_proceeds(
         &self,
         symbol: &str,
-        shares_to_sell: f64,
+        shares_to_sell: Decimal,
         sell_date: &NaiveDate,
-    ) {
-        let available_shares_at_sell_date: f64 = self
-            .equity_award_center
-            .awards
-            .iter()
-            .filter(|award| award.symbol == symbol)
-            .filter(|award| award.date_acquired <= sell_date.to_owned())
-            .map(|award| award.available_to_sell)
-            .sum();
-
-        if shares_to_sell > available_shares_at_sell_date {
-            panic!(
-                "You tried to sell {} {} shares, but there are only {} available before {}!",
-                shares_to_sell, symbol, available_shares_at_sell_date, sell_date
-            );
-        }
-    }
-
-    fn calculate_proceeds(&self, symbol: &str, shares_to_sell: f64, sell_date: &NaiveDate) -> f64 {
+    ) -> Decimal {
         let sell_price = self
             .stock_price_provider
             .get_historic_price(symbol, sell_date)
             .expect(format!("Missing stock price for date: {:?}", sell_date).as_str());
 
+        let today = Utc::now().naive_utc().date();
+        let splits = self.split_provider.get_splits(symbol);
+        let sell_date_basis_price = sell_price * cumulative_split_factor(&splits, sell_date, &today);
+
         self.exchange_rate_provider
-            .to_gbp(sell_price * shares_to_sell, sell_date)
+            .to_gbp(sell_date_basis_price * shares_to_sell, sell_date)
             .expect(format!("Missing exchange rate for date: {:?}", sell_date).as_str())
     }
 
+    /// Splits a disposal into HMRC's same-day, bed & breakfast and
+    /// section 104 tiers, in that priority order.
     fn calculate_costs(
         &self,
         symbol: &str,
-        shares_to_sell: f64,
+        shares_to_sell: Decimal,
         sell_date: &NaiveDate,
-    ) -> (f64, f64) {
+    ) -> (Decimal, Decimal, Decimal) {
+        let same_day_shares = self.count_same_day_shares(symbol, shares_to_sell, sell_date);
+        let remaining_after_same_day = shares_to_sell - same_day_shares;
+
         let bed_and_breakfast_lookahead_date = sell_date.to_owned() + Duration::days(30);
         let bed_and_breakfast_shares = self.count_bed_and_breakfast_shares(
             symbol,
-            shares_to_sell,
+            remaining_after_same_day,
             sell_date,
             &bed_and_breakfast_lookahead_date,
         );
-        let section_104_holding_shares = shares_to_sell - bed_and_breakfast_shares;
+        let section_104_holding_shares = remaining_after_same_day - bed_and_breakfast_shares;
 
         (
+            self.calculate_same_day_cost(symbol, same_day_shares, sell_date),
             self.calculate_bed_and_breakfast_cost(
                 symbol,
                 bed_and_breakfast_shares,
@@ -154,23 +218,78 @@ impl CGTCalculator {
         )
     }
 
+    fn count_same_day_shares(
+        &self,
+        symbol: &str,
+        shares_to_sell: Decimal,
+        sell_date: &NaiveDate,
+    ) -> Decimal {
+        let same_day_shares: Decimal = self
+            .equity_award_center
+            .awards
+            .iter()
+            .filter(|award| award.symbol == symbol)
+            .filter(|award| award.date_acquired == sell_date.to_owned())
+            .map(|award| self.split_adjusted_available_to_sell(award, sell_date))
+            .sum();
+
+        same_day_shares.min(shares_to_sell)
+    }
+
+    /// Pools same-day acquisitions at their weighted-average price.
+    fn calculate_same_day_cost(
+        &self,
+        symbol: &str,
+        shares_to_sell: Decimal,
+        sell_date: &NaiveDate,
+    ) -> Decimal {
+        let same_day_awards = self
+            .equity_award_center
+            .awards
+            .iter()
+            .filter(|award| award.symbol == symbol)
+            .filter(|award| award.date_acquired == sell_date.to_owned())
+            .collect::<Vec<_>>();
+
+        let total_cost: Decimal = same_day_awards
+            .iter()
+            .map(|award| {
+                let available_to_sell = self.split_adjusted_available_to_sell(award, sell_date);
+                let acquisition_price = self.split_adjusted_acquisition_price(award, sell_date);
+                self.exchange_rate_provider
+                    .to_gbp(available_to_sell * acquisition_price, sell_date)
+                    .expect(format!("Missing exchange rate for date: {:?}", sell_date).as_str())
+            })
+            .sum();
+        let total_shares: Decimal = same_day_awards
+            .iter()
+            .map(|award| self.split_adjusted_available_to_sell(award, sell_date))
+            .sum();
+
+        if total_shares.is_zero() {
+            return Decimal::ZERO;
+        }
+
+        (total_cost / total_shares) * shares_to_sell
+    }
+
     fn count_bed_and_breakfast_shares(
         &self,
         symbol: &str,
-        shares_to_sell: f64,
+        shares_to_sell: Decimal,
         sell_date: &NaiveDate,
         lookahead_date: &NaiveDate,
-    ) -> f64 {
-        let lookahead_shares: f64 = self
+    ) -> Decimal {
+        let lookahead_shares: Decimal = self
             .equity_award_center
             .awards
             .iter()
             .filter(|award| award.symbol == symbol)
             .filter(|award| {
-                sell_date.to_owned() <= award.date_acquired.to_owned()
+                sell_date.to_owned() < award.date_acquired.to_owned()
                     && award.date_acquired.to_owned() <= lookahead_date.to_owned()
             })
-            .map(|award| award.available_to_sell)
+            .map(|award| self.split_adjusted_available_to_sell(award, sell_date))
             .sum();
 
         lookahead_shares.min(shares_to_sell)
@@ -179,32 +298,31 @@ impl CGTCalculator {
     fn calculate_bed_and_breakfast_cost(
         &self,
         symbol: &str,
-        shares_to_sell: f64,
+        shares_to_sell: Decimal,
         sell_date: &NaiveDate,
         lookahead_date: &NaiveDate,
-    ) -> f64 {
+    ) -> Decimal {
         let mut lookahead_awards = self
             .equity_award_center
             .awards
             .iter()
             .filter(|award| award.symbol == symbol)
             .filter(|award| {
-                sell_date.to_owned() <= award.date_acquired.to_owned()
+                sell_date.to_owned() < award.date_acquired.to_owned()
                     && award.date_acquired.to_owned() <= lookahead_date.to_owned()
             })
             .collect::<Vec<_>>();
         lookahead_awards.sort_by_key(|award| award.date_acquired);
 
-        let mut total_cost = 0.0;
+        let mut total_cost = Decimal::ZERO;
         let mut remaining_shares_to_fill = shares_to_sell;
         for award in lookahead_awards {
-            let shares_to_fill = remaining_shares_to_fill.min(award.available_to_sell);
+            let available_to_sell = self.split_adjusted_available_to_sell(award, sell_date);
+            let acquisition_price = self.split_adjusted_acquisition_price(award, sell_date);
+            let shares_to_fill = remaining_shares_to_fill.min(available_to_sell);
             total_cost += self
                 .exchange_rate_provider
-                .to_gbp(
-                    shares_to_fill * award.acquisition_price,
-                    &award.date_acquired,
-                )
+                .to_gbp(shares_to_fill * acquisition_price, &award.date_acquired)
                 .expect(
                     format!("Missing exchange rate for date: {:?}", &award.date_acquired).as_str(),
                 );
@@ -216,9 +334,9 @@ impl CGTCalculator {
     fn calculate_section_104_holding_cost(
         &self,
         symbol: &str,
-        shares_to_sell: f64,
+        shares_to_sell: Decimal,
         sell_date: &NaiveDate,
-    ) -> f64 {
+    ) -> Decimal {
         let awards_before_sell_date = self
             .equity_award_center
             .awards
@@ -227,26 +345,591 @@ impl CGTCalculator {
             .filter(|award| award.date_acquired < sell_date.to_owned())
             .collect::<Vec<_>>();
 
-        let total_cost_before_sell_date: f64 = awards_before_sell_date
+        let mut total_cost_before_sell_date: Decimal = awards_before_sell_date
             .iter()
             .map(|award| {
+                let available_to_sell = self.split_adjusted_available_to_sell(award, sell_date);
+                let acquisition_price = self.split_adjusted_acquisition_price(award, sell_date);
                 self.exchange_rate_provider
-                    .to_gbp(
-                        award.available_to_sell * award.acquisition_price,
-                        &award.date_acquired,
-                    )
+                    .to_gbp(available_to_sell * acquisition_price, &award.date_acquired)
                     .expect(
                         format!("Missing exchange rate for date: {:?}", &award.date_acquired)
                             .as_str(),
                     )
             })
             .sum();
-        let total_shares_before_sell_date: f64 = awards_before_sell_date
+        let mut total_shares_before_sell_date: Decimal = awards_before_sell_date
             .iter()
-            .map(|award| award.available_to_sell)
+            .map(|award| self.split_adjusted_available_to_sell(award, sell_date))
             .sum();
+
+        let (drip_cost, drip_shares) = self.drip_pool_additions(symbol, sell_date);
+        total_cost_before_sell_date += drip_cost;
+        total_shares_before_sell_date += drip_shares;
+
+        if total_shares_before_sell_date.is_zero() {
+            // No pre-existing section 104 pool, e.g. selling newly-vested
+            // shares on vest day with no prior holdings of this symbol.
+            return Decimal::ZERO;
+        }
         let avg_cost = total_cost_before_sell_date / total_shares_before_sell_date;
 
         avg_cost * shares_to_sell
     }
+
+    /// DRIP's extra section 104 pool cost and shares from dividends paid
+    /// before `sell_date`, or zero if DRIP mode is disabled.
+    fn drip_pool_additions(&self, symbol: &str, sell_date: &NaiveDate) -> (Decimal, Decimal) {
+        let Some(dividend_provider) = self.dividend_provider.as_ref() else {
+            return (Decimal::ZERO, Decimal::ZERO);
+        };
+
+        let mut total_cost = Decimal::ZERO;
+        let mut total_shares = Decimal::ZERO;
+        for dividend in dividend_provider.get_dividends(symbol) {
+            if dividend.ex_date >= sell_date.to_owned() {
+                continue;
+            }
+
+            let held_shares_at_ex_date: Decimal = self
+                .equity_award_center
+                .awards
+                .iter()
+                .filter(|award| award.symbol == symbol)
+                .filter(|award| award.date_acquired <= dividend.ex_date)
+                .map(|award| self.split_adjusted_available_to_sell(award, &dividend.ex_date))
+                .sum();
+            if held_shares_at_ex_date.is_zero() {
+                continue;
+            }
+
+            let dividend_cash = held_shares_at_ex_date * dividend.amount_per_share;
+            // Undo Adj Close the same way calculate_proceeds does.
+            let today = Utc::now().naive_utc().date();
+            let splits = self.split_provider.get_splits(symbol);
+            let raw_reinvestment_price = self
+                .stock_price_provider
+                .get_historic_price(symbol, &dividend.ex_date)
+                .expect(
+                    format!("Missing stock price for date: {:?}", &dividend.ex_date).as_str(),
+                );
+            let ex_date_basis_price =
+                raw_reinvestment_price * cumulative_split_factor(&splits, &dividend.ex_date, &today);
+
+            total_cost += self
+                .exchange_rate_provider
+                .to_gbp(dividend_cash, &dividend.ex_date)
+                .expect(
+                    format!("Missing exchange rate for date: {:?}", &dividend.ex_date).as_str(),
+                );
+
+            // Bring the reinvested shares onto sell_date's basis.
+            let reinvested_shares = dividend_cash / ex_date_basis_price;
+            let split_factor_to_sell_date =
+                cumulative_split_factor(&splits, &dividend.ex_date, sell_date);
+            total_shares += reinvested_shares * split_factor_to_sell_date;
+        }
+
+        (total_cost, total_shares)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stock::dividends::Dividend;
+    use crate::stock::splits::Split;
+    use std::str::FromStr;
+
+    struct FixedStockPriceProvider {
+        price: Decimal,
+    }
+
+    impl StockPriceProvider for FixedStockPriceProvider {
+        fn get_historic_price(&self, _symbol: &str, _date: &NaiveDate) -> Option<Decimal> {
+            Some(self.price)
+        }
+    }
+
+    struct FixedExchangeRateProvider;
+
+    impl ExchangeRateProvider for FixedExchangeRateProvider {
+        fn to_gbp(&self, usd: Decimal, _date: &NaiveDate) -> Option<Decimal> {
+            Some(usd)
+        }
+    }
+
+    struct NoSplitProvider;
+
+    impl SplitProvider for NoSplitProvider {
+        fn get_splits(&self, _symbol: &str) -> Vec<Split> {
+            Vec::new()
+        }
+    }
+
+    struct FixedSplitProvider {
+        splits: Vec<Split>,
+    }
+
+    impl SplitProvider for FixedSplitProvider {
+        fn get_splits(&self, _symbol: &str) -> Vec<Split> {
+            self.splits.clone()
+        }
+    }
+
+    struct FixedDividendProvider {
+        dividends: Vec<Dividend>,
+    }
+
+    impl DividendProvider for FixedDividendProvider {
+        fn get_dividends(&self, _symbol: &str) -> Vec<Dividend> {
+            self.dividends.clone()
+        }
+    }
+
+    /// Mimics Yahoo's real Adj Close behaviour: `raw_price` is the actual
+    /// historic price, retroactively divided by every split between the
+    /// queried date and today. Unlike `FixedStockPriceProvider`, the
+    /// returned price genuinely varies with the date argument, so a caller
+    /// that forgets to undo the Adj Close adjustment will see it.
+    struct AdjCloseStockPriceProvider {
+        raw_price: Decimal,
+        splits: Vec<Split>,
+    }
+
+    impl StockPriceProvider for AdjCloseStockPriceProvider {
+        fn get_historic_price(&self, _symbol: &str, date: &NaiveDate) -> Option<Decimal> {
+            let today = Utc::now().naive_utc().date();
+            Some(self.raw_price / cumulative_split_factor(&self.splits, date, &today))
+        }
+    }
+
+    fn test_calculator(awards: Vec<EquityAward>, price: Decimal) -> CGTCalculator {
+        CGTCalculator {
+            equity_award_center: EquityAwardCenter { awards },
+            stock_price_provider: Box::new(FixedStockPriceProvider { price }),
+            exchange_rate_provider: Box::new(FixedExchangeRateProvider),
+            split_provider: Box::new(NoSplitProvider),
+            dividend_provider: None,
+        }
+    }
+
+    #[test]
+    fn test_same_day_only_disposal_with_empty_pool_does_not_panic() {
+        // Newly-vested RSUs sold on vest day: no prior holdings of this
+        // symbol, so the section 104 pool is empty.
+        let sell_date = NaiveDate::from_ymd(2024, 1, 15);
+        let awards = vec![EquityAward {
+            symbol: "GOOG".to_owned(),
+            date_acquired: sell_date,
+            acquisition_price: Decimal::from_str("100").unwrap(),
+            available_to_sell: Decimal::from_str("10").unwrap(),
+        }];
+        let calculator = test_calculator(awards, Decimal::from_str("150").unwrap());
+
+        let result = calculator.calculate_cgt(
+            "GOOG",
+            Decimal::from_str("10").unwrap(),
+            &sell_date,
+            Decimal::ZERO,
+        ).unwrap();
+
+        assert_eq!(result.section_104_holding_cost, Decimal::ZERO);
+        assert_eq!(result.same_day_cost, Decimal::from_str("1000").unwrap());
+        assert_eq!(result.proceeds, Decimal::from_str("1500").unwrap());
+    }
+
+    #[test]
+    fn test_same_day_cost_is_pooled_at_the_weighted_average_price() {
+        // Two lots vesting on the same day at different prices, e.g. an RSU
+        // tranche and an ESPP lot, with a disposal smaller than either lot.
+        let sell_date = NaiveDate::from_ymd(2024, 1, 15);
+        let awards = vec![
+            EquityAward {
+                symbol: "GOOG".to_owned(),
+                date_acquired: sell_date,
+                acquisition_price: Decimal::from_str("100").unwrap(),
+                available_to_sell: Decimal::from_str("100").unwrap(),
+            },
+            EquityAward {
+                symbol: "GOOG".to_owned(),
+                date_acquired: sell_date,
+                acquisition_price: Decimal::from_str("120").unwrap(),
+                available_to_sell: Decimal::from_str("100").unwrap(),
+            },
+        ];
+        let calculator = test_calculator(awards, Decimal::from_str("200").unwrap());
+
+        let result = calculator.calculate_cgt(
+            "GOOG",
+            Decimal::from_str("50").unwrap(),
+            &sell_date,
+            Decimal::ZERO,
+        ).unwrap();
+
+        // Pooled cost is 50 shares at the weighted-average price of £110
+        // ((100*100 + 100*120) / 200), not 50 * £100 or 50 * £120 depending
+        // on which award happens to come first.
+        assert_eq!(result.same_day_cost, Decimal::from_str("5500").unwrap());
+        assert_eq!(result.section_104_holding_cost, Decimal::ZERO);
+        assert_eq!(result.bed_and_breakfast_cost, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_same_day_award_is_not_double_counted_in_bed_and_breakfast_tier() {
+        // One award acquired on sell_date itself, selling more shares than
+        // that award covers with nothing else in the portfolio. The
+        // remainder must fall through to an empty section 104 pool, not get
+        // re-matched against the same award a second time in the b&b tier.
+        let sell_date = NaiveDate::from_ymd(2024, 1, 15);
+        let awards = vec![EquityAward {
+            symbol: "GOOG".to_owned(),
+            date_acquired: sell_date,
+            acquisition_price: Decimal::from_str("100").unwrap(),
+            available_to_sell: Decimal::from_str("5").unwrap(),
+        }];
+        let calculator = test_calculator(awards, Decimal::from_str("150").unwrap());
+
+        let result = calculator
+            .calculate_cgt(
+                "GOOG",
+                Decimal::from_str("10").unwrap(),
+                &sell_date,
+                Decimal::ZERO,
+            )
+            .unwrap();
+
+        assert_eq!(result.same_day_cost, Decimal::from_str("500").unwrap());
+        assert_eq!(result.bed_and_breakfast_cost, Decimal::ZERO);
+        assert_eq!(result.section_104_holding_cost, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_same_day_award_is_not_double_counted_alongside_a_genuine_bed_and_breakfast_award() {
+        // A same-day award covers half the disposal and a genuinely
+        // forward-dated award (acquired after sell_date, within the 30-day
+        // window) covers the rest. The same-day award's shares must not
+        // also be swept into the b&b tier's lookahead filter.
+        let sell_date = NaiveDate::from_ymd(2024, 1, 15);
+        let awards = vec![
+            EquityAward {
+                symbol: "GOOG".to_owned(),
+                date_acquired: sell_date,
+                acquisition_price: Decimal::from_str("100").unwrap(),
+                available_to_sell: Decimal::from_str("5").unwrap(),
+            },
+            EquityAward {
+                symbol: "GOOG".to_owned(),
+                date_acquired: sell_date + Duration::days(10),
+                acquisition_price: Decimal::from_str("200").unwrap(),
+                available_to_sell: Decimal::from_str("5").unwrap(),
+            },
+        ];
+        let calculator = test_calculator(awards, Decimal::from_str("150").unwrap());
+
+        let result = calculator
+            .calculate_cgt(
+                "GOOG",
+                Decimal::from_str("10").unwrap(),
+                &sell_date,
+                Decimal::ZERO,
+            )
+            .unwrap();
+
+        assert_eq!(result.same_day_cost, Decimal::from_str("500").unwrap());
+        assert_eq!(
+            result.bed_and_breakfast_cost,
+            Decimal::from_str("1000").unwrap()
+        );
+        assert_eq!(result.section_104_holding_cost, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_drip_pool_additions_are_split_adjusted_to_sell_date() {
+        // One award, one dividend paid before a 2:1 split, sold entirely
+        // out of the section 104 pool after the split.
+        let date_acquired = NaiveDate::from_ymd(2020, 1, 1);
+        let ex_date = NaiveDate::from_ymd(2020, 6, 1);
+        let split_date = NaiveDate::from_ymd(2021, 1, 1);
+        let sell_date = NaiveDate::from_ymd(2022, 1, 1);
+
+        let awards = vec![EquityAward {
+            symbol: "GOOG".to_owned(),
+            date_acquired,
+            acquisition_price: Decimal::from_str("50").unwrap(),
+            available_to_sell: Decimal::from_str("10").unwrap(),
+        }];
+
+        let splits = vec![Split {
+            date: split_date,
+            numerator: Decimal::from(2),
+            denominator: Decimal::from(1),
+        }];
+
+        let calculator = CGTCalculator {
+            equity_award_center: EquityAwardCenter { awards },
+            stock_price_provider: Box::new(AdjCloseStockPriceProvider {
+                raw_price: Decimal::from_str("5").unwrap(),
+                splits: splits.clone(),
+            }),
+            exchange_rate_provider: Box::new(FixedExchangeRateProvider),
+            split_provider: Box::new(FixedSplitProvider { splits }),
+            dividend_provider: Some(Box::new(FixedDividendProvider {
+                dividends: vec![Dividend {
+                    ex_date,
+                    amount_per_share: Decimal::from_str("1").unwrap(),
+                }],
+            })),
+        };
+
+        // 10 pre-split shares become 20 post-split; the whole pool is sold.
+        let result = calculator.calculate_cgt(
+            "GOOG",
+            Decimal::from_str("20").unwrap(),
+            &sell_date,
+            Decimal::ZERO,
+        ).unwrap();
+
+        // Pool cost: 20 shares * £25 adjusted acquisition price = £500,
+        // plus the DRIP's £10 dividend cash, over 20 + 4 = 24 pooled shares
+        // (the 2 ex-date-basis reinvested shares become 4 once split-adjusted
+        // to the sell date), giving an average cost of £21.25/share.
+        assert_eq!(
+            result.section_104_holding_cost,
+            Decimal::from_str("425").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_drip_reinvestment_price_undoes_adj_close_bias_from_a_later_split() {
+        // The dividend is paid in 2020, sold in 2022, and the only split
+        // happens in 2023 — after sell_date, so it must not affect
+        // split_factor_to_sell_date at all. But Yahoo's Adj Close still
+        // bakes that later split into every price query, including the one
+        // for ex_date, so reinvestment_price must be un-adjusted before
+        // it's used to size the reinvested shares.
+        let date_acquired = NaiveDate::from_ymd(2020, 1, 1);
+        let ex_date = NaiveDate::from_ymd(2020, 6, 1);
+        let sell_date = NaiveDate::from_ymd(2022, 1, 1);
+        let split_date = NaiveDate::from_ymd(2023, 1, 1);
+
+        let awards = vec![EquityAward {
+            symbol: "GOOG".to_owned(),
+            date_acquired,
+            acquisition_price: Decimal::from_str("50").unwrap(),
+            available_to_sell: Decimal::from_str("10").unwrap(),
+        }];
+
+        let splits = vec![Split {
+            date: split_date,
+            numerator: Decimal::from(2),
+            denominator: Decimal::from(1),
+        }];
+
+        let calculator = CGTCalculator {
+            equity_award_center: EquityAwardCenter { awards },
+            stock_price_provider: Box::new(AdjCloseStockPriceProvider {
+                raw_price: Decimal::from_str("5").unwrap(),
+                splits: splits.clone(),
+            }),
+            exchange_rate_provider: Box::new(FixedExchangeRateProvider),
+            split_provider: Box::new(FixedSplitProvider { splits }),
+            dividend_provider: Some(Box::new(FixedDividendProvider {
+                dividends: vec![Dividend {
+                    ex_date,
+                    amount_per_share: Decimal::from_str("1").unwrap(),
+                }],
+            })),
+        };
+
+        let result = calculator
+            .calculate_cgt(
+                "GOOG",
+                Decimal::from_str("10").unwrap(),
+                &sell_date,
+                Decimal::ZERO,
+            )
+            .unwrap();
+
+        // Pool cost: 10 shares * £50 = £500, plus the DRIP's £10 dividend
+        // cash, over 10 + 2 = 12 pooled shares (£10 cash / £5 true ex-date
+        // price = 2 reinvested shares; the 2023 split is after sell_date so
+        // it doesn't scale them further), giving an average cost of
+        // £42.50/share. Before the fix, the un-undone Adj Close (£2.50,
+        // already halved by the 2023 split) doubled the reinvested share
+        // count to 4, diluting the average cost to ~£36.43/share.
+        assert_eq!(
+            result.section_104_holding_cost,
+            Decimal::from_str("425").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_bed_and_breakfast_award_split_factor_is_inverted_for_a_forward_dated_acquisition() {
+        // A bed & breakfast award is acquired 19 days after the sale, with a
+        // 2:1 split falling in between. The award's quantity/price are
+        // recorded on its own (post-split) basis and must be converted back
+        // to the sell date's (pre-split) basis using the inverted factor,
+        // not the forward cumulative_split_factor.
+        let sell_date = NaiveDate::from_ymd(2024, 1, 1);
+        let split_date = NaiveDate::from_ymd(2024, 1, 10);
+        let date_acquired = NaiveDate::from_ymd(2024, 1, 20);
+
+        let awards = vec![
+            // Pre-existing section 104 holding so the sale itself is backed
+            // by shares already owned at sell_date; the b&b award below is
+            // the later repurchase HMRC's 30-day rule matches it against.
+            EquityAward {
+                symbol: "GOOG".to_owned(),
+                date_acquired: NaiveDate::from_ymd(2019, 1, 1),
+                acquisition_price: Decimal::from_str("10").unwrap(),
+                available_to_sell: Decimal::from_str("10").unwrap(),
+            },
+            EquityAward {
+                symbol: "GOOG".to_owned(),
+                date_acquired,
+                acquisition_price: Decimal::from_str("50").unwrap(),
+                available_to_sell: Decimal::from_str("20").unwrap(),
+            },
+        ];
+
+        let calculator = CGTCalculator {
+            equity_award_center: EquityAwardCenter { awards },
+            stock_price_provider: Box::new(FixedStockPriceProvider {
+                price: Decimal::from_str("150").unwrap(),
+            }),
+            exchange_rate_provider: Box::new(FixedExchangeRateProvider),
+            split_provider: Box::new(FixedSplitProvider {
+                splits: vec![Split {
+                    date: split_date,
+                    numerator: Decimal::from(2),
+                    denominator: Decimal::from(1),
+                }],
+            }),
+            dividend_provider: None,
+        };
+
+        let result = calculator
+            .calculate_cgt(
+                "GOOG",
+                Decimal::from_str("10").unwrap(),
+                &sell_date,
+                Decimal::ZERO,
+            )
+            .unwrap();
+
+        // Sell-date-basis: 20 post-split shares / 2 = 10 shares available,
+        // at 50 * 2 = £100/share pre-split-equivalent price. All 10 sold
+        // shares match against this award for a cost of £1,000.
+        assert_eq!(
+            result.bed_and_breakfast_cost,
+            Decimal::from_str("1000").unwrap()
+        );
+        assert_eq!(result.same_day_cost, Decimal::ZERO);
+        assert_eq!(result.section_104_holding_cost, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_calculate_proceeds_undoes_a_split_that_fell_after_the_sell_date() {
+        // get_historic_price always returns Yahoo's Adj Close, retroactively
+        // adjusted for every split up to today. A 2022 sell date with a 2:1
+        // split later in 2023 means the quoted price is already halved
+        // relative to the real 2022 price, so it must be doubled back before
+        // multiplying by the (real, sell-date-basis) share count.
+        let sell_date = NaiveDate::from_ymd(2022, 6, 1);
+        let split_date = NaiveDate::from_ymd(2023, 1, 1);
+        let awards = vec![EquityAward {
+            symbol: "GOOG".to_owned(),
+            date_acquired: NaiveDate::from_ymd(2018, 1, 1),
+            acquisition_price: Decimal::from_str("10").unwrap(),
+            available_to_sell: Decimal::from_str("100").unwrap(),
+        }];
+
+        let calculator = CGTCalculator {
+            equity_award_center: EquityAwardCenter { awards },
+            stock_price_provider: Box::new(FixedStockPriceProvider {
+                price: Decimal::from_str("70").unwrap(),
+            }),
+            exchange_rate_provider: Box::new(FixedExchangeRateProvider),
+            split_provider: Box::new(FixedSplitProvider {
+                splits: vec![Split {
+                    date: split_date,
+                    numerator: Decimal::from(2),
+                    denominator: Decimal::from(1),
+                }],
+            }),
+            dividend_provider: None,
+        };
+
+        let result = calculator
+            .calculate_cgt(
+                "GOOG",
+                Decimal::from_str("10").unwrap(),
+                &sell_date,
+                Decimal::ZERO,
+            )
+            .unwrap();
+
+        // 10 real shares * (£70 quoted * 2, to undo the later 2:1 split) = £1,400.
+        assert_eq!(result.proceeds, Decimal::from_str("1400").unwrap());
+    }
+
+    #[test]
+    fn test_calculate_cgt_splits_the_gain_across_the_basic_and_higher_rate_bands() {
+        // A single section 104 pool bought well before the sale, so the
+        // whole disposal is straightforward pool cost with no same-day or
+        // bed & breakfast matching.
+        let sell_date = NaiveDate::from_ymd(2022, 1, 1);
+        let awards = vec![EquityAward {
+            symbol: "GOOG".to_owned(),
+            date_acquired: NaiveDate::from_ymd(2020, 1, 1),
+            acquisition_price: Decimal::from_str("10").unwrap(),
+            available_to_sell: Decimal::from_str("1000").unwrap(),
+        }];
+        let calculator = test_calculator(awards, Decimal::from_str("1000").unwrap());
+
+        // Proceeds £100,000, pool cost £1,000, annual exempt amount £12,300
+        // (2021/22 tax year) leaves £86,700 subject to CGT. With a £10,000
+        // remaining basic-rate band, £10,000 is taxed at 10% and the
+        // remaining £76,700 at 20%.
+        let result = calculator
+            .calculate_cgt(
+                "GOOG",
+                Decimal::from_str("100").unwrap(),
+                &sell_date,
+                Decimal::from_str("10000").unwrap(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            result.amount_taxed_at_basic_rate,
+            Decimal::from_str("10000").unwrap()
+        );
+        assert_eq!(
+            result.amount_taxed_at_higher_rate,
+            Decimal::from_str("76700").unwrap()
+        );
+        assert_eq!(result.cgt, Decimal::from_str("16340").unwrap());
+    }
+
+    #[test]
+    fn test_calculate_cgt_errors_instead_of_panicking_for_a_pre_table_sell_date() {
+        let sell_date = NaiveDate::from_ymd(2000, 1, 1);
+        let awards = vec![EquityAward {
+            symbol: "GOOG".to_owned(),
+            date_acquired: NaiveDate::from_ymd(1999, 1, 1),
+            acquisition_price: Decimal::from_str("10").unwrap(),
+            available_to_sell: Decimal::from_str("100").unwrap(),
+        }];
+        let calculator = test_calculator(awards, Decimal::from_str("20").unwrap());
+
+        let result = calculator.calculate_cgt(
+            "GOOG",
+            Decimal::from_str("10").unwrap(),
+            &sell_date,
+            Decimal::ZERO,
+        );
+
+        assert!(result.is_err());
+    }
 }
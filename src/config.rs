@@ -0,0 +1,123 @@
+use std::fs;
+
+use anyhow::Result;
+use chrono::Duration;
+use serde::Deserialize;
+
+/// Where a symbol's historic close prices are fetched from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StockPriceBackend {
+    Yahoo,
+    AlphaVantage,
+    Finnhub,
+    TwelveData,
+}
+
+impl Default for StockPriceBackend {
+    fn default() -> Self {
+        StockPriceBackend::Yahoo
+    }
+}
+
+impl StockPriceBackend {
+    /// Kebab-case identifier, used to scope the on-disk cache by backend.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StockPriceBackend::Yahoo => "yahoo",
+            StockPriceBackend::AlphaVantage => "alpha-vantage",
+            StockPriceBackend::Finnhub => "finnhub",
+            StockPriceBackend::TwelveData => "twelve-data",
+        }
+    }
+}
+
+/// Where historic USD/GBP exchange rates are fetched from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ExchangeRateBackend {
+    Yahoo,
+    AlphaVantage,
+    Finnhub,
+    TwelveData,
+}
+
+impl Default for ExchangeRateBackend {
+    fn default() -> Self {
+        ExchangeRateBackend::Yahoo
+    }
+}
+
+impl ExchangeRateBackend {
+    /// Kebab-case identifier, used to scope the on-disk cache by backend.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExchangeRateBackend::Yahoo => "yahoo",
+            ExchangeRateBackend::AlphaVantage => "alpha-vantage",
+            ExchangeRateBackend::Finnhub => "finnhub",
+            ExchangeRateBackend::TwelveData => "twelve-data",
+        }
+    }
+}
+
+/// User-supplied configuration for market-data backends and their local
+/// cache, loaded from a TOML file via [`Config::load_from_file`].
+#[derive(Debug, Deserialize)]
+pub struct Config {
+    /// Directory that cached price/rate histories are persisted to.
+    #[serde(default = "default_cache_dir")]
+    pub cache_dir: String,
+
+    /// How long a cached series stays fresh before it is re-fetched.
+    #[serde(default = "default_cache_expire_time_seconds")]
+    pub cache_expire_time_seconds: i64,
+
+    #[serde(default)]
+    pub stock_price_backend: StockPriceBackend,
+
+    #[serde(default)]
+    pub exchange_rate_backend: ExchangeRateBackend,
+
+    /// When true, reinvested dividends are folded into the section 104 pool
+    /// as extra cost and extra shares (dividend reinvestment plan mode).
+    #[serde(default)]
+    pub drip_enabled: bool,
+
+    pub alpha_vantage_api_key: Option<String>,
+    pub finnhub_api_key: Option<String>,
+    pub twelve_data_api_key: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            cache_dir: default_cache_dir(),
+            cache_expire_time_seconds: default_cache_expire_time_seconds(),
+            stock_price_backend: StockPriceBackend::default(),
+            exchange_rate_backend: ExchangeRateBackend::default(),
+            drip_enabled: false,
+            alpha_vantage_api_key: None,
+            finnhub_api_key: None,
+            twelve_data_api_key: None,
+        }
+    }
+}
+
+fn default_cache_dir() -> String {
+    ".schwab-cgt-calculator-cache".to_owned()
+}
+
+fn default_cache_expire_time_seconds() -> i64 {
+    Duration::hours(24).num_seconds()
+}
+
+impl Config {
+    pub fn load_from_file(path_to_toml: &str) -> Result<Self> {
+        let contents = fs::read_to_string(path_to_toml)?;
+        Ok(toml::from_str(&contents)?)
+    }
+
+    pub fn cache_expire_time(&self) -> Duration {
+        Duration::seconds(self.cache_expire_time_seconds)
+    }
+}
@@ -1,33 +1,107 @@
 use chrono::NaiveDate;
+use rust_decimal::Decimal;
 use std::collections::BTreeMap;
 
+use crate::{
+    cache::DiskCache,
+    config::{Config, ExchangeRateBackend},
+    market_data,
+};
+
 pub trait ExchangeRateProvider {
-    fn to_gbp(&self, usd: f64, date: &NaiveDate) -> Option<f64>;
+    fn to_gbp(&self, usd: Decimal, date: &NaiveDate) -> Option<Decimal>;
 }
 
-pub struct YahooExchangeRateProvider {
-    historic_rates: BTreeMap<NaiveDate, f64>,
+const CACHE_KEY: &str = "USDGBP";
+
+/// Builds the [`ExchangeRateProvider`] configured by
+/// `config.exchange_rate_backend`, serving the USD/GBP rate history out of
+/// the on-disk cache when a fresh entry exists and persisting it after a
+/// live fetch otherwise.
+pub async fn build_exchange_rate_provider(config: &Config) -> Box<dyn ExchangeRateProvider> {
+    let cache = DiskCache::new(
+        format!(
+            "{}/exchange-rates/{}",
+            config.cache_dir,
+            config.exchange_rate_backend.as_str()
+        ),
+        config.cache_expire_time(),
+    );
+
+    let historic_rates = match cache.get(CACHE_KEY) {
+        Some(historic_rates) => historic_rates,
+        None => {
+            let historic_rates = fetch_historic_rates(config).await;
+            cache.put(CACHE_KEY, &historic_rates);
+            historic_rates
+        }
+    };
+
+    Box::new(HistoricExchangeRateProvider { historic_rates })
 }
 
-impl YahooExchangeRateProvider {
-    pub async fn new() -> Self {
-        let mut historic_rates: BTreeMap<NaiveDate, f64> = BTreeMap::new();
-        let url = "https://query1.finance.yahoo.com/v7/finance/download/USDGBP=X?period1=0&period2=9999999999&interval=1d&events=history&includeAdjustedClose=true";
-        let body = reqwest::get(url).await.unwrap().text().await.unwrap();
-        let mut reader = csv::Reader::from_reader(body.as_bytes());
-        for result in reader.records() {
-            let record = result.unwrap();
-            let date = NaiveDate::parse_from_str(&record[0], "%Y-%m-%d")
-                .expect(format!("Failed to parse date from string: {:?}", &record[0]).as_str());
-            let close: Option<f64> = record[5].parse().ok();
-            close.map(|c| historic_rates.insert(date, c));
+async fn fetch_historic_rates(config: &Config) -> BTreeMap<NaiveDate, Decimal> {
+    match config.exchange_rate_backend {
+        ExchangeRateBackend::Yahoo => fetch_yahoo_rates().await,
+        ExchangeRateBackend::AlphaVantage => {
+            let api_key = config
+                .alpha_vantage_api_key
+                .as_deref()
+                .expect("alpha_vantage_api_key is required for the AlphaVantage backend");
+            fetch_alpha_vantage_rates(api_key).await
+        }
+        ExchangeRateBackend::Finnhub => {
+            let api_key = config
+                .finnhub_api_key
+                .as_deref()
+                .expect("finnhub_api_key is required for the Finnhub backend");
+            fetch_finnhub_rates(api_key).await
+        }
+        ExchangeRateBackend::TwelveData => {
+            let api_key = config
+                .twelve_data_api_key
+                .as_deref()
+                .expect("twelve_data_api_key is required for the TwelveData backend");
+            fetch_twelve_data_rates(api_key).await
         }
-        Self { historic_rates }
     }
 }
 
-impl ExchangeRateProvider for YahooExchangeRateProvider {
-    fn to_gbp(&self, usd: f64, date: &NaiveDate) -> Option<f64> {
+async fn fetch_yahoo_rates() -> BTreeMap<NaiveDate, Decimal> {
+    let url = "https://query1.finance.yahoo.com/v7/finance/download/USDGBP=X?period1=0&period2=9999999999&interval=1d&events=history&includeAdjustedClose=true";
+    market_data::fetch_yahoo_series(url, 5).await
+}
+
+async fn fetch_alpha_vantage_rates(api_key: &str) -> BTreeMap<NaiveDate, Decimal> {
+    let url = format!(
+        "https://www.alphavantage.co/query?function=FX_DAILY&from_symbol=USD&to_symbol=GBP&outputsize=full&apikey={}",
+        api_key
+    );
+    market_data::fetch_alpha_vantage_series(&url, "Time Series FX (Daily)").await
+}
+
+async fn fetch_finnhub_rates(api_key: &str) -> BTreeMap<NaiveDate, Decimal> {
+    let url = format!(
+        "https://finnhub.io/api/v1/forex/candle?symbol=OANDA:USD_GBP&resolution=D&from=0&to=9999999999&token={}",
+        api_key
+    );
+    market_data::fetch_finnhub_series(&url).await
+}
+
+async fn fetch_twelve_data_rates(api_key: &str) -> BTreeMap<NaiveDate, Decimal> {
+    let url = format!(
+        "https://api.twelvedata.com/time_series?symbol=USD/GBP&interval=1day&outputsize=5000&apikey={}",
+        api_key
+    );
+    market_data::fetch_twelve_data_series(&url).await
+}
+
+struct HistoricExchangeRateProvider {
+    historic_rates: BTreeMap<NaiveDate, Decimal>,
+}
+
+impl ExchangeRateProvider for HistoricExchangeRateProvider {
+    fn to_gbp(&self, usd: Decimal, date: &NaiveDate) -> Option<Decimal> {
         let maybe_rate = if self.historic_rates.contains_key(date) {
             self.historic_rates.get(date)
         } else {
@@ -44,20 +118,25 @@ impl ExchangeRateProvider for YahooExchangeRateProvider {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::str::FromStr;
 
     #[tokio::test]
     async fn test_to_gbp() {
-        let provider = YahooExchangeRateProvider::new().await;
+        let historic_rates = fetch_yahoo_rates().await;
+        let provider = HistoricExchangeRateProvider { historic_rates };
         assert_eq!(
-            provider.to_gbp(1.0, &NaiveDate::from_ymd(2003, 12, 1)),
-            Some(0.581870)
+            provider.to_gbp(Decimal::ONE, &NaiveDate::from_ymd(2003, 12, 1)),
+            Some(Decimal::from_str("0.581870").unwrap())
         );
         // Use data from Friday if query falls on a weekend
         assert_eq!(
-            provider.to_gbp(1.0, &NaiveDate::from_ymd(2003, 12, 7)),
-            Some(0.577000)
+            provider.to_gbp(Decimal::ONE, &NaiveDate::from_ymd(2003, 12, 7)),
+            Some(Decimal::from_str("0.577000").unwrap())
         );
         // Earliest available date is 2003-12-01
-        assert_eq!(provider.to_gbp(1.0, &NaiveDate::from_ymd(1900, 1, 1)), None);
+        assert_eq!(
+            provider.to_gbp(Decimal::ONE, &NaiveDate::from_ymd(1900, 1, 1)),
+            None
+        );
     }
 }
@@ -1,11 +1,22 @@
 use chrono::NaiveDate;
 use clap::{Parser, ValueEnum};
+use rust_decimal::Decimal;
 use schwab_cgt_calculator::{
-    calculator::CGTCalculator, schwab::equity_award_center::EquityAwardCenter,
+    calculator::CGTCalculator,
+    config::Config,
+    reporting::{self, OutputFormat},
+    schwab::equity_award_center::EquityAwardCenter,
 };
 
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(
+    author,
+    version,
+    about,
+    long_about = "Breaking change: --taxpayer-status is gone. Pass --remaining-basic-rate-band \
+                  instead (defaults to 0, i.e. entirely higher-rate); old `--taxpayer-status \
+                  Basic` users should pass their full basic-rate band to match prior behaviour."
+)]
 struct Args {
     /// Stock symbol
     #[arg(long)]
@@ -17,44 +28,64 @@ struct Args {
 
     /// Number of shares to sell
     #[arg(long)]
-    shares_to_sell: f64,
+    shares_to_sell: Decimal,
 
     /// Path to EquityAwardsCenter_EquityDetails_yyyymmxxxxxx.csv file.
     #[arg(long)]
     path_to_csv: String,
 
-    /// Annual exemption amount (£12,300 for 2022)
-    #[arg(long, default_value_t = 12300.0)]
-    annual_exemption_amount: f64,
+    /// Remaining basic-rate band for sell_date's tax year
+    #[arg(long, default_value = "0")]
+    remaining_basic_rate_band: Decimal,
 
-    ///  Taxpayer status (Basic rate - 10% / Higher rate - 20%)
-    #[arg(long, value_enum)]
-    taxpayer_status: TaxpayerStatus,
+    /// Path to a TOML config file (market-data backends, API keys, cache)
+    #[arg(long)]
+    config: Option<String>,
+
+    /// Output format: human-readable text, or Ledger-CLI postings
+    #[arg(long, value_enum, default_value = "text")]
+    output_format: OutputFormatArg,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum OutputFormatArg {
+    Text,
+    Ledger,
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
-enum TaxpayerStatus {
-    Basic,
-    Higher,
+impl From<OutputFormatArg> for OutputFormat {
+    fn from(arg: OutputFormatArg) -> Self {
+        match arg {
+            OutputFormatArg::Text => OutputFormat::Text,
+            OutputFormatArg::Ledger => OutputFormat::Ledger,
+        }
+    }
 }
 
 #[tokio::main(flavor = "current_thread")]
-async fn main() {
+async fn main() -> anyhow::Result<()> {
     let args = Args::parse();
 
-    let cgt_rate = match args.taxpayer_status {
-        TaxpayerStatus::Basic => 0.1,
-        TaxpayerStatus::Higher => 0.2,
+    let config = match &args.config {
+        Some(path_to_config) => Config::load_from_file(path_to_config).unwrap(),
+        None => Config::default(),
     };
 
     let calculator = CGTCalculator::new(
         &args.symbol,
         EquityAwardCenter::parse_from_csv(&args.path_to_csv).unwrap(),
-        args.annual_exemption_amount,
-        cgt_rate,
+        &config,
     )
-    .await;
-    let cgt_result = calculator.calculate_cgt(&args.symbol, args.shares_to_sell, &args.sell_date);
+    .await?;
+    let cgt_result = calculator
+        .calculate_cgt(
+            &args.symbol,
+            args.shares_to_sell,
+            &args.sell_date,
+            args.remaining_basic_rate_band,
+        )
+        .unwrap();
 
-    println!("{}", cgt_result.to_string())
+    print!("{}", reporting::render(&cgt_result, args.output_format.into()));
+    Ok(())
 }
@@ -0,0 +1,101 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+/// Parses a Yahoo Finance CSV date column (`%Y-%m-%d`).
+pub fn parse_yahoo_date(date_str: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
+        .expect(format!("Failed to parse date from string: {:?}", date_str).as_str())
+}
+
+/// Downloads a Yahoo Finance `/v7/finance/download` CSV export (prices,
+/// dividends or splits, depending on `events=...` in `url`) as parsed CSV
+/// records.
+pub async fn fetch_yahoo_csv_records(url: &str) -> Vec<csv::StringRecord> {
+    let body = reqwest::get(url).await.unwrap().text().await.unwrap();
+    let mut reader = csv::Reader::from_reader(body.as_bytes());
+    reader.records().map(|result| result.unwrap()).collect()
+}
+
+/// Downloads a Yahoo Finance price/rate history CSV into a date series,
+/// reading the close value from `value_column`.
+pub async fn fetch_yahoo_series(url: &str, value_column: usize) -> BTreeMap<NaiveDate, Decimal> {
+    let mut series = BTreeMap::new();
+    for record in fetch_yahoo_csv_records(url).await {
+        let date = parse_yahoo_date(&record[0]);
+        let value: Option<Decimal> = record[value_column].parse().ok();
+        value.map(|v| series.insert(date, v));
+    }
+    series
+}
+
+/// Downloads an Alpha Vantage daily time-series JSON response into a date
+/// series, reading the close value from `"4. close"` under `json_path`.
+pub async fn fetch_alpha_vantage_series(
+    url: &str,
+    json_path: &str,
+) -> BTreeMap<NaiveDate, Decimal> {
+    let mut series = BTreeMap::new();
+    let body = reqwest::get(url).await.unwrap().text().await.unwrap();
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    if let Some(entries) = json[json_path].as_object() {
+        for (date_str, values) in entries {
+            let date = parse_yahoo_date(date_str);
+            let value: Option<Decimal> = values["4. close"].as_str().and_then(|s| s.parse().ok());
+            value.map(|v| series.insert(date, v));
+        }
+    }
+    series
+}
+
+/// Downloads a Finnhub `/candle`-shaped JSON response (parallel `c` close
+/// and `t` Unix-second-timestamp arrays) into a date series.
+pub async fn fetch_finnhub_series(url: &str) -> BTreeMap<NaiveDate, Decimal> {
+    let mut series = BTreeMap::new();
+    let body = reqwest::get(url).await.unwrap().text().await.unwrap();
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    let closes = json["c"].as_array().cloned().unwrap_or_default();
+    let timestamps = json["t"].as_array().cloned().unwrap_or_default();
+    for (close, timestamp) in closes.iter().zip(timestamps.iter()) {
+        let date = timestamp.as_i64().and_then(|secs| {
+            i32::try_from(secs / 86400 + 719163)
+                .ok()
+                .and_then(NaiveDate::from_num_days_from_ce_opt)
+        });
+        let close: Option<Decimal> = close.as_f64().and_then(|c| Decimal::try_from(c).ok());
+        if let (Some(date), Some(close)) = (date, close) {
+            series.insert(date, close);
+        }
+    }
+    series
+}
+
+/// Downloads a Twelve Data `/time_series`-shaped JSON response (a `values`
+/// array of `{datetime, close}` objects) into a date series.
+pub async fn fetch_twelve_data_series(url: &str) -> BTreeMap<NaiveDate, Decimal> {
+    let mut series = BTreeMap::new();
+    let body = reqwest::get(url).await.unwrap().text().await.unwrap();
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    if let Some(values) = json["values"].as_array() {
+        for value in values {
+            let date = value["datetime"]
+                .as_str()
+                .and_then(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok());
+            let close: Option<Decimal> = value["close"].as_str().and_then(|s| s.parse().ok());
+            if let (Some(date), Some(close)) = (date, close) {
+                series.insert(date, close);
+            }
+        }
+    }
+    series
+}
+
+/// Panics if `actual` doesn't match `expected`.
+pub fn assert_symbol_matches(kind: &str, expected: &str, actual: &str) {
+    assert_eq!(
+        actual, expected,
+        "This {} provider only supports {}, not {}!",
+        kind, expected, actual
+    );
+}
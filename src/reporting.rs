@@ -0,0 +1,137 @@
+use std::fmt::Write;
+
+use crate::calculator::CGTCalculatorResult;
+
+/// How a [`CGTCalculatorResult`] is rendered for the user.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// The existing human-readable summary (`CGTCalculatorResult`'s `Display` impl).
+    Text,
+    /// Double-entry postings in Ledger-CLI plain-text format, suitable for
+    /// appending to an existing ledger file.
+    Ledger,
+}
+
+pub fn render(result: &CGTCalculatorResult, format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Text => result.to_string(),
+        OutputFormat::Ledger => render_ledger(result),
+    }
+}
+
+/// Renders `result` as two Ledger-CLI transactions: the disposal itself
+/// (crediting the asset account for each cost-basis lot and the realised
+/// gain/loss to a capital-gains income account, debiting cash for the
+/// proceeds), and the resulting CGT liability.
+fn render_ledger(result: &CGTCalculatorResult) -> String {
+    let mut ledger = String::new();
+    let date = result.sell_date.format("%Y-%m-%d");
+    let asset_account = format!("Assets:Investments:{}", result.symbol);
+    let realised_gain = result.proceeds
+        - result.same_day_cost
+        - result.bed_and_breakfast_cost
+        - result.section_104_holding_cost;
+
+    writeln!(
+        ledger,
+        "{} * Disposal of {} {}",
+        date, result.shares_sold, result.symbol
+    )
+    .unwrap();
+    writeln!(
+        ledger,
+        "    Assets:Cash:GBP                              £{:.2}",
+        result.proceeds.round_dp(2)
+    )
+    .unwrap();
+    if !result.same_day_cost.is_zero() {
+        writeln!(
+            ledger,
+            "    {}                £-{:.2}  ; same-day",
+            asset_account,
+            result.same_day_cost.round_dp(2)
+        )
+        .unwrap();
+    }
+    if !result.bed_and_breakfast_cost.is_zero() {
+        writeln!(
+            ledger,
+            "    {}                £-{:.2}  ; bed & breakfast",
+            asset_account,
+            result.bed_and_breakfast_cost.round_dp(2)
+        )
+        .unwrap();
+    }
+    if !result.section_104_holding_cost.is_zero() {
+        writeln!(
+            ledger,
+            "    {}                £-{:.2}  ; section 104",
+            asset_account,
+            result.section_104_holding_cost.round_dp(2)
+        )
+        .unwrap();
+    }
+    writeln!(
+        ledger,
+        "    Income:CapitalGains:{}                      £{:.2}",
+        result.symbol,
+        (-realised_gain).round_dp(2)
+    )
+    .unwrap();
+
+    writeln!(ledger).unwrap();
+    writeln!(
+        ledger,
+        "{} * CGT due on disposal of {}",
+        date, result.symbol
+    )
+    .unwrap();
+    writeln!(
+        ledger,
+        "    Expenses:Tax:CapitalGains                    £{:.2}",
+        result.cgt.round_dp(2)
+    )
+    .unwrap();
+    writeln!(
+        ledger,
+        "    Liabilities:Tax:CapitalGains                 £-{:.2}",
+        result.cgt.round_dp(2)
+    )
+    .unwrap();
+
+    ledger
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use rust_decimal::Decimal;
+    use std::str::FromStr;
+
+    fn loss_making_result() -> CGTCalculatorResult {
+        CGTCalculatorResult {
+            symbol: "GOOG".to_owned(),
+            sell_date: NaiveDate::from_ymd(2024, 1, 15),
+            shares_sold: Decimal::from_str("10").unwrap(),
+            cgt: Decimal::ZERO,
+            proceeds: Decimal::from_str("100").unwrap(),
+            same_day_cost: Decimal::ZERO,
+            bed_and_breakfast_cost: Decimal::ZERO,
+            section_104_holding_cost: Decimal::from_str("150").unwrap(),
+            amount_subject_to_cgt: Decimal::ZERO,
+            amount_taxed_at_basic_rate: Decimal::ZERO,
+            amount_taxed_at_higher_rate: Decimal::ZERO,
+            basic_rate: Decimal::new(18, 2),
+            higher_rate: Decimal::new(24, 2),
+        }
+    }
+
+    #[test]
+    fn test_render_ledger_formats_a_loss_without_a_double_minus() {
+        let ledger = render(&loss_making_result(), OutputFormat::Ledger);
+
+        assert!(!ledger.contains("£--"));
+        assert!(ledger.contains("Income:CapitalGains:GOOG                      £50.00"));
+    }
+}
@@ -1,5 +1,6 @@
 use anyhow::Result;
 use chrono::NaiveDate;
+use rust_decimal::Decimal;
 
 #[derive(Debug)]
 pub struct EquityAwardCenter {
@@ -10,8 +11,8 @@ pub struct EquityAwardCenter {
 pub struct EquityAward {
     pub symbol: String,
     pub date_acquired: NaiveDate,
-    pub acquisition_price: f64,
-    pub available_to_sell: f64,
+    pub acquisition_price: Decimal,
+    pub available_to_sell: Decimal,
 }
 
 impl EquityAwardCenter {
@@ -67,8 +68,9 @@ impl EquityAwardCenter {
                 if is_record {
                     let symbol = record[1].to_owned();
                     let date_acquired = NaiveDate::parse_from_str(&record[7], "%m-%d-%Y").unwrap();
-                    let acquisition_price: f64 = record[8].replace("$", "").parse().unwrap();
-                    let available_to_sell: f64 = record[10].parse().unwrap();
+                    let acquisition_price: Decimal =
+                        record[8].replace("$", "").parse().unwrap();
+                    let available_to_sell: Decimal = record[10].parse().unwrap();
 
                     let award = EquityAward {
                         symbol,
@@ -88,6 +90,7 @@ impl EquityAwardCenter {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::str::FromStr;
 
     #[test]
     fn test_parser() {
@@ -101,8 +104,8 @@ mod tests {
             &EquityAward {
                 symbol: String::from("GOOG"),
                 date_acquired: NaiveDate::from_ymd(2018, 11, 26),
-                acquisition_price: 51.194,
-                available_to_sell: 42.42
+                acquisition_price: Decimal::from_str("51.194").unwrap(),
+                available_to_sell: Decimal::from_str("42.42").unwrap()
             }
         );
         assert_eq!(
@@ -110,8 +113,8 @@ mod tests {
             &EquityAward {
                 symbol: String::from("GOOG"),
                 date_acquired: NaiveDate::from_ymd(2022, 9, 25),
-                acquisition_price: 99.17,
-                available_to_sell: 10.351
+                acquisition_price: Decimal::from_str("99.17").unwrap(),
+                available_to_sell: Decimal::from_str("10.351").unwrap()
             }
         );
     }
@@ -0,0 +1,89 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cache::DiskCache,
+    config::{Config, StockPriceBackend},
+    market_data,
+};
+
+/// A cash dividend of `amount_per_share` (in the stock's listing currency)
+/// paid to holders of record as of `ex_date`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Dividend {
+    pub ex_date: NaiveDate,
+    pub amount_per_share: Decimal,
+}
+
+pub trait DividendProvider {
+    fn get_dividends(&self, symbol: &str) -> Vec<Dividend>;
+}
+
+/// Builds the [`DividendProvider`] for `symbol`, serving its dividend
+/// history out of the on-disk cache when a fresh entry exists and
+/// persisting it after a live fetch otherwise.
+pub async fn build_dividend_provider(
+    symbol: &str,
+    config: &Config,
+) -> anyhow::Result<Box<dyn DividendProvider>> {
+    let cache = DiskCache::new(
+        format!(
+            "{}/dividends/{}",
+            config.cache_dir,
+            config.stock_price_backend.as_str()
+        ),
+        config.cache_expire_time(),
+    );
+
+    let dividends = match cache.get(symbol) {
+        Some(dividends) => dividends,
+        None => {
+            let dividends = fetch_dividends(symbol, config).await?;
+            cache.put(symbol, &dividends);
+            dividends
+        }
+    };
+
+    Ok(Box::new(HistoricDividendProvider {
+        symbol: symbol.to_owned(),
+        dividends,
+    }))
+}
+
+async fn fetch_dividends(symbol: &str, config: &Config) -> anyhow::Result<Vec<Dividend>> {
+    match config.stock_price_backend {
+        StockPriceBackend::Yahoo => Ok(fetch_yahoo_dividends(symbol).await),
+        other => anyhow::bail!(
+            "dividend history is only available from the Yahoo backend, not {:?}",
+            other
+        ),
+    }
+}
+
+async fn fetch_yahoo_dividends(symbol: &str) -> Vec<Dividend> {
+    let url = format!("https://query1.finance.yahoo.com/v7/finance/download/{}?period1=0&period2=9999999999&interval=1d&events=div&includeAdjustedClose=true", symbol);
+    market_data::fetch_yahoo_csv_records(&url)
+        .await
+        .into_iter()
+        .filter_map(|record| {
+            let ex_date = market_data::parse_yahoo_date(&record[0]);
+            record[1].parse().ok().map(|amount_per_share| Dividend {
+                ex_date,
+                amount_per_share,
+            })
+        })
+        .collect()
+}
+
+struct HistoricDividendProvider {
+    symbol: String,
+    dividends: Vec<Dividend>,
+}
+
+impl DividendProvider for HistoricDividendProvider {
+    fn get_dividends(&self, symbol: &str) -> Vec<Dividend> {
+        market_data::assert_symbol_matches("dividend", &self.symbol, symbol);
+        self.dividends.clone()
+    }
+}
@@ -1,43 +1,113 @@
 use std::collections::BTreeMap;
 
 use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+use crate::{
+    cache::DiskCache,
+    config::{Config, StockPriceBackend},
+    market_data,
+};
 
 pub trait StockPriceProvider {
-    fn get_historic_price(&self, symbol: &str, date: &NaiveDate) -> Option<f64>;
+    fn get_historic_price(&self, symbol: &str, date: &NaiveDate) -> Option<Decimal>;
 }
 
-pub struct YahooStockPriceProvider {
-    symbol: String,
-    historic_prices: BTreeMap<NaiveDate, f64>,
+/// Builds the [`StockPriceProvider`] configured by `config.stock_price_backend`
+/// for `symbol`, serving the price history out of the on-disk cache when a
+/// fresh entry exists and persisting it after a live fetch otherwise.
+pub async fn build_stock_price_provider(
+    symbol: &str,
+    config: &Config,
+) -> Box<dyn StockPriceProvider> {
+    let cache = DiskCache::new(
+        format!(
+            "{}/stock-prices/{}",
+            config.cache_dir,
+            config.stock_price_backend.as_str()
+        ),
+        config.cache_expire_time(),
+    );
+
+    let historic_prices = match cache.get(symbol) {
+        Some(historic_prices) => historic_prices,
+        None => {
+            let historic_prices = fetch_historic_prices(symbol, config).await;
+            cache.put(symbol, &historic_prices);
+            historic_prices
+        }
+    };
+
+    Box::new(HistoricStockPriceProvider {
+        symbol: symbol.to_owned(),
+        historic_prices,
+    })
 }
 
-impl YahooStockPriceProvider {
-    pub async fn new(symbol: &str) -> Self {
-        let mut historic_prices: BTreeMap<NaiveDate, f64> = BTreeMap::new();
-        let url = format!("https://query1.finance.yahoo.com/v7/finance/download/{}?period1=0&period2=9999999999&interval=1d&events=history&includeAdjustedClose=true", symbol);
-        let body = reqwest::get(url).await.unwrap().text().await.unwrap();
-        let mut reader = csv::Reader::from_reader(body.as_bytes());
-        for result in reader.records() {
-            let record = result.unwrap();
-            let date = NaiveDate::parse_from_str(&record[0], "%Y-%m-%d")
-                .expect(format!("Failed to parse date from string: {:?}", &record[0]).as_str());
-            let close: Option<f64> = record[5].parse().ok();
-            close.map(|c| historic_prices.insert(date, c));
+async fn fetch_historic_prices(symbol: &str, config: &Config) -> BTreeMap<NaiveDate, Decimal> {
+    match config.stock_price_backend {
+        StockPriceBackend::Yahoo => fetch_yahoo_prices(symbol).await,
+        StockPriceBackend::AlphaVantage => {
+            let api_key = config
+                .alpha_vantage_api_key
+                .as_deref()
+                .expect("alpha_vantage_api_key is required for the AlphaVantage backend");
+            fetch_alpha_vantage_prices(symbol, api_key).await
         }
-        Self {
-            symbol: symbol.to_owned(),
-            historic_prices,
+        StockPriceBackend::Finnhub => {
+            let api_key = config
+                .finnhub_api_key
+                .as_deref()
+                .expect("finnhub_api_key is required for the Finnhub backend");
+            fetch_finnhub_prices(symbol, api_key).await
+        }
+        StockPriceBackend::TwelveData => {
+            let api_key = config
+                .twelve_data_api_key
+                .as_deref()
+                .expect("twelve_data_api_key is required for the TwelveData backend");
+            fetch_twelve_data_prices(symbol, api_key).await
         }
     }
 }
 
-impl StockPriceProvider for YahooStockPriceProvider {
-    fn get_historic_price(&self, symbol: &str, date: &NaiveDate) -> Option<f64> {
-        assert_eq!(
-            symbol, self.symbol,
-            "This stock price provider only supports {}, not {}!",
-            self.symbol, symbol
-        );
+async fn fetch_yahoo_prices(symbol: &str) -> BTreeMap<NaiveDate, Decimal> {
+    let url = format!("https://query1.finance.yahoo.com/v7/finance/download/{}?period1=0&period2=9999999999&interval=1d&events=history&includeAdjustedClose=true", symbol);
+    market_data::fetch_yahoo_series(&url, 5).await
+}
+
+async fn fetch_alpha_vantage_prices(symbol: &str, api_key: &str) -> BTreeMap<NaiveDate, Decimal> {
+    let url = format!(
+        "https://www.alphavantage.co/query?function=TIME_SERIES_DAILY&symbol={}&outputsize=full&apikey={}",
+        symbol, api_key
+    );
+    market_data::fetch_alpha_vantage_series(&url, "Time Series (Daily)").await
+}
+
+async fn fetch_finnhub_prices(symbol: &str, api_key: &str) -> BTreeMap<NaiveDate, Decimal> {
+    let url = format!(
+        "https://finnhub.io/api/v1/stock/candle?symbol={}&resolution=D&from=0&to=9999999999&token={}",
+        symbol, api_key
+    );
+    market_data::fetch_finnhub_series(&url).await
+}
+
+async fn fetch_twelve_data_prices(symbol: &str, api_key: &str) -> BTreeMap<NaiveDate, Decimal> {
+    let url = format!(
+        "https://api.twelvedata.com/time_series?symbol={}&interval=1day&outputsize=5000&apikey={}",
+        symbol, api_key
+    );
+    market_data::fetch_twelve_data_series(&url).await
+}
+
+struct HistoricStockPriceProvider {
+    symbol: String,
+    historic_prices: BTreeMap<NaiveDate, Decimal>,
+}
+
+impl StockPriceProvider for HistoricStockPriceProvider {
+    fn get_historic_price(&self, symbol: &str, date: &NaiveDate) -> Option<Decimal> {
+        market_data::assert_symbol_matches("stock price", &self.symbol, symbol);
 
         let maybe_price = if self.historic_prices.contains_key(date) {
             self.historic_prices.get(date)
@@ -55,13 +125,18 @@ impl StockPriceProvider for YahooStockPriceProvider {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::str::FromStr;
 
     #[tokio::test]
     async fn test_get_historic_price() {
-        let provider = YahooStockPriceProvider::new("GOOG").await;
+        let historic_prices = fetch_yahoo_prices("GOOG").await;
+        let provider = HistoricStockPriceProvider {
+            symbol: "GOOG".to_owned(),
+            historic_prices,
+        };
         assert_eq!(
             provider.get_historic_price("GOOG", &NaiveDate::from_ymd(2004, 08, 19)),
-            Some(2.499133)
+            Some(Decimal::from_str("2.499133").unwrap())
         );
         // Earliest available date is 2004-08-19
         assert_eq!(
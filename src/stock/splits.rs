@@ -0,0 +1,157 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cache::DiskCache,
+    config::{Config, StockPriceBackend},
+    market_data,
+};
+
+/// A stock split (or reverse split) taking effect on `date`: one pre-split
+/// share becomes `numerator / denominator` post-split shares.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Split {
+    pub date: NaiveDate,
+    pub numerator: Decimal,
+    pub denominator: Decimal,
+}
+
+impl Split {
+    pub fn factor(&self) -> Decimal {
+        self.numerator / self.denominator
+    }
+}
+
+pub trait SplitProvider {
+    fn get_splits(&self, symbol: &str) -> Vec<Split>;
+}
+
+/// Builds the [`SplitProvider`] for `symbol`, serving its split history out
+/// of the on-disk cache when a fresh entry exists and persisting it after a
+/// live fetch otherwise.
+pub async fn build_split_provider(
+    symbol: &str,
+    config: &Config,
+) -> anyhow::Result<Box<dyn SplitProvider>> {
+    let cache = DiskCache::new(
+        format!(
+            "{}/splits/{}",
+            config.cache_dir,
+            config.stock_price_backend.as_str()
+        ),
+        config.cache_expire_time(),
+    );
+
+    let splits = match cache.get(symbol) {
+        Some(splits) => splits,
+        None => {
+            let splits = fetch_splits(symbol, config).await?;
+            cache.put(symbol, &splits);
+            splits
+        }
+    };
+
+    Ok(Box::new(HistoricSplitProvider {
+        symbol: symbol.to_owned(),
+        splits,
+    }))
+}
+
+async fn fetch_splits(symbol: &str, config: &Config) -> anyhow::Result<Vec<Split>> {
+    match config.stock_price_backend {
+        StockPriceBackend::Yahoo => Ok(fetch_yahoo_splits(symbol).await),
+        other => anyhow::bail!(
+            "split history is only available from the Yahoo backend, not {:?}",
+            other
+        ),
+    }
+}
+
+async fn fetch_yahoo_splits(symbol: &str) -> Vec<Split> {
+    let url = format!("https://query1.finance.yahoo.com/v7/finance/download/{}?period1=0&period2=9999999999&interval=1d&events=split&includeAdjustedClose=true", symbol);
+    market_data::fetch_yahoo_csv_records(&url)
+        .await
+        .into_iter()
+        .filter_map(|record| {
+            let date = market_data::parse_yahoo_date(&record[0]);
+            // Yahoo reports splits as "numerator:denominator", e.g. "20:1".
+            record[1].split_once(':').map(|(numerator, denominator)| Split {
+                date,
+                numerator: numerator.parse().unwrap(),
+                denominator: denominator.parse().unwrap(),
+            })
+        })
+        .collect()
+}
+
+struct HistoricSplitProvider {
+    symbol: String,
+    splits: Vec<Split>,
+}
+
+impl SplitProvider for HistoricSplitProvider {
+    fn get_splits(&self, symbol: &str) -> Vec<Split> {
+        market_data::assert_symbol_matches("split", &self.symbol, symbol);
+        self.splits.clone()
+    }
+}
+
+/// Cumulative multiplier turning a share count from the basis in effect
+/// just after `from_date` (exclusive) into the basis in effect on `to_date`
+/// (inclusive), by compounding every split that fell in between. A
+/// per-share price converts between the same bases by dividing by it.
+pub fn cumulative_split_factor(
+    splits: &[Split],
+    from_date: &NaiveDate,
+    to_date: &NaiveDate,
+) -> Decimal {
+    splits
+        .iter()
+        .filter(|split| from_date < &split.date && &split.date <= to_date)
+        .fold(Decimal::ONE, |factor, split| factor * split.factor())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(date: NaiveDate, numerator: i64, denominator: i64) -> Split {
+        Split {
+            date,
+            numerator: Decimal::from(numerator),
+            denominator: Decimal::from(denominator),
+        }
+    }
+
+    #[test]
+    fn test_cumulative_split_factor_ignores_splits_outside_range() {
+        let splits = vec![split(NaiveDate::from_ymd(2022, 7, 18), 20, 1)];
+
+        assert_eq!(
+            cumulative_split_factor(
+                &splits,
+                &NaiveDate::from_ymd(2022, 8, 1),
+                &NaiveDate::from_ymd(2022, 9, 1),
+            ),
+            Decimal::ONE
+        );
+    }
+
+    #[test]
+    fn test_cumulative_split_factor_compounds_multiple_splits() {
+        let splits = vec![
+            split(NaiveDate::from_ymd(2014, 4, 3), 2, 1),
+            split(NaiveDate::from_ymd(2022, 7, 18), 20, 1),
+        ];
+
+        assert_eq!(
+            cumulative_split_factor(
+                &splits,
+                &NaiveDate::from_ymd(2010, 1, 1),
+                &NaiveDate::from_ymd(2022, 12, 1),
+            ),
+            Decimal::from(40)
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+
+/// The CGT annual exempt amount and basic/higher rates applicable to a UK
+/// tax year (6 April to 5 April).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TaxYearRates {
+    pub annual_exempt_amount: Decimal,
+    pub basic_rate: Decimal,
+    pub higher_rate: Decimal,
+}
+
+/// Looks up the [`TaxYearRates`] in effect for the UK tax year containing
+/// `date`, i.e. the entry whose start date is the latest one on or before
+/// `date`. Entries start on 6 April, except where rates changed mid-year
+/// (2024-10-30), which gets its own entry. Returns `None` if `date` predates
+/// the earliest known tax year in the table.
+pub fn rates_for_date(date: &NaiveDate) -> Option<TaxYearRates> {
+    tax_year_table()
+        .range(..=date.to_owned())
+        .next_back()
+        .map(|(_start_date, rates)| rates.to_owned())
+}
+
+fn tax_year_table() -> BTreeMap<NaiveDate, TaxYearRates> {
+    let mut table = BTreeMap::new();
+    table.insert(
+        NaiveDate::from_ymd(2021, 4, 6),
+        TaxYearRates {
+            annual_exempt_amount: Decimal::from(12300),
+            basic_rate: Decimal::new(10, 2),
+            higher_rate: Decimal::new(20, 2),
+        },
+    );
+    table.insert(
+        NaiveDate::from_ymd(2023, 4, 6),
+        TaxYearRates {
+            annual_exempt_amount: Decimal::from(6000),
+            basic_rate: Decimal::new(10, 2),
+            higher_rate: Decimal::new(20, 2),
+        },
+    );
+    table.insert(
+        NaiveDate::from_ymd(2024, 4, 6),
+        TaxYearRates {
+            annual_exempt_amount: Decimal::from(3000),
+            basic_rate: Decimal::new(10, 2),
+            higher_rate: Decimal::new(20, 2),
+        },
+    );
+    // Autumn Budget 2024: rates on shares rose to 18%/24% for disposals on
+    // or after 2024-10-30, mid-way through the 2024/25 tax year.
+    table.insert(
+        NaiveDate::from_ymd(2024, 10, 30),
+        TaxYearRates {
+            annual_exempt_amount: Decimal::from(3000),
+            basic_rate: Decimal::new(18, 2),
+            higher_rate: Decimal::new(24, 2),
+        },
+    );
+    table.insert(
+        NaiveDate::from_ymd(2025, 4, 6),
+        TaxYearRates {
+            annual_exempt_amount: Decimal::from(3000),
+            basic_rate: Decimal::new(18, 2),
+            higher_rate: Decimal::new(24, 2),
+        },
+    );
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rates_for_date_picks_correct_tax_year() {
+        assert_eq!(
+            rates_for_date(&NaiveDate::from_ymd(2022, 1, 1))
+                .unwrap()
+                .annual_exempt_amount,
+            Decimal::from(12300)
+        );
+        assert_eq!(
+            rates_for_date(&NaiveDate::from_ymd(2023, 4, 6))
+                .unwrap()
+                .annual_exempt_amount,
+            Decimal::from(6000)
+        );
+        assert_eq!(
+            rates_for_date(&NaiveDate::from_ymd(2024, 4, 5))
+                .unwrap()
+                .annual_exempt_amount,
+            Decimal::from(6000)
+        );
+    }
+
+    #[test]
+    fn test_rates_for_date_before_earliest_entry_is_none() {
+        assert_eq!(rates_for_date(&NaiveDate::from_ymd(2000, 1, 1)), None);
+    }
+
+    #[test]
+    fn test_rates_for_date_picks_up_autumn_budget_2024_rate_rise() {
+        // Day before the change: still the old 10%/20% rates.
+        assert_eq!(
+            rates_for_date(&NaiveDate::from_ymd(2024, 10, 29))
+                .unwrap()
+                .higher_rate,
+            Decimal::new(20, 2)
+        );
+        // On and after 2024-10-30: the new 18%/24% rates, carrying into 2025/26.
+        assert_eq!(
+            rates_for_date(&NaiveDate::from_ymd(2024, 10, 30)).unwrap(),
+            TaxYearRates {
+                annual_exempt_amount: Decimal::from(3000),
+                basic_rate: Decimal::new(18, 2),
+                higher_rate: Decimal::new(24, 2),
+            }
+        );
+        assert_eq!(
+            rates_for_date(&NaiveDate::from_ymd(2026, 7, 30))
+                .unwrap()
+                .higher_rate,
+            Decimal::new(24, 2)
+        );
+    }
+}